@@ -0,0 +1,158 @@
+use crate::filter::MetadataFilter;
+use crate::sort::{Sort, SortKey};
+use camino::Utf8PathBuf;
+use ignore::overrides::OverrideBuilder;
+use ignore::types::{FileTypeDef, TypesBuilder};
+use ignore::{Walk, WalkBuilder};
+
+/// Which entries of a directory to walk.
+#[derive(Debug, Clone)]
+pub struct WalkConfig {
+    /// Directory to walk.
+    pub dir: Utf8PathBuf,
+    /// Maximum depth to recurse into directories.
+    pub max_depth: Option<usize>,
+    /// Include hidden files.
+    pub hidden: bool,
+    /// Respect `.gitignore`, `.ignore` and global gitignore files.
+    pub respect_ignore: bool,
+    /// Order of the entries within each directory.
+    pub sort: Sort,
+    /// Only include files matching at least one of these globs, if any are given.
+    pub patterns: Vec<String>,
+    /// Exclude entries matching any of these globs.
+    pub excludes: Vec<String>,
+    /// Only include files of these types, if any are given.
+    pub types: Vec<String>,
+    /// Exclude files of these types.
+    pub types_not: Vec<String>,
+    /// Extra type definitions, in ripgrep's `name:glob` syntax.
+    pub type_defs: Vec<String>,
+    /// Limits on the size and age of files.
+    pub filter: MetadataFilter,
+    /// Only include directories.
+    pub dirs_only: bool,
+}
+
+impl WalkConfig {
+    pub fn new(dir: impl Into<Utf8PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            max_depth: None,
+            hidden: false,
+            respect_ignore: true,
+            sort: Sort::default(),
+            patterns: Vec::new(),
+            excludes: Vec::new(),
+            types: Vec::new(),
+            types_not: Vec::new(),
+            type_defs: Vec::new(),
+            filter: MetadataFilter::default(),
+            dirs_only: false,
+        }
+    }
+
+    /// Returns the known file types, including those from `type_defs`.
+    pub fn type_definitions(&self) -> Result<Vec<FileTypeDef>, ignore::Error> {
+        Ok(self.types_builder()?.definitions())
+    }
+
+    fn types_builder(&self) -> Result<TypesBuilder, ignore::Error> {
+        let mut types = TypesBuilder::new();
+
+        types.add_defaults();
+
+        for def in &self.type_defs {
+            types.add_def(def)?;
+        }
+
+        Ok(types)
+    }
+
+    /// Builds the walker, which yields the root directory first.
+    ///
+    /// Fails if any of the patterns is not a valid glob, or any of the types
+    /// is unknown or badly defined.
+    pub fn build(&self) -> Result<Walk, ignore::Error> {
+        self.build_walker(true)
+    }
+
+    /// Builds the walker like [`WalkConfig::build`], but leaves sorts that
+    /// [need metadata](Sort::needs_metadata) to the caller, which avoids
+    /// reading the metadata of every entry once per comparison.
+    pub(crate) fn build_unsorted_by_metadata(&self) -> Result<Walk, ignore::Error> {
+        self.build_walker(false)
+    }
+
+    fn build_walker(&self, sort_by_metadata: bool) -> Result<Walk, ignore::Error> {
+        // Patterns only narrow down the walk, so they are matched by hand
+        // rather than as overrides, which would take precedence over ignore
+        // files, hidden files and types.
+        let mut patterns = OverrideBuilder::new(&self.dir);
+
+        for pattern in &self.patterns {
+            patterns.add(pattern)?;
+        }
+
+        let patterns = patterns.build()?;
+
+        let mut overrides = OverrideBuilder::new(&self.dir);
+
+        for exclude in &self.excludes {
+            overrides.add(&format!("!{exclude}"))?;
+        }
+
+        let mut types = self.types_builder()?;
+
+        for name in &self.types {
+            types.select(name);
+        }
+
+        for name in &self.types_not {
+            types.negate(name);
+        }
+
+        let mut builder = WalkBuilder::new(&self.dir);
+
+        builder
+            .ignore(self.respect_ignore)
+            .git_ignore(self.respect_ignore)
+            .git_global(self.respect_ignore)
+            .max_depth(self.max_depth)
+            .hidden(!self.hidden)
+            .overrides(overrides.build()?)
+            .types(types.build()?)
+            .skip_stdout(true);
+
+        if self.dirs_only || !self.filter.is_empty() || !patterns.is_empty() {
+            let dirs_only = self.dirs_only;
+            let filter = self.filter;
+
+            builder.filter_entry(move |entry| {
+                if entry.file_type().is_some_and(|t| t.is_dir()) {
+                    return true;
+                }
+
+                !dirs_only
+                    && (patterns.is_empty() || patterns.matched(entry.path(), false).is_whitelist())
+                    && (filter.is_empty() || entry.metadata().is_ok_and(|m| filter.matches(&m)))
+            });
+        }
+
+        let sort = self.sort;
+
+        if sort.needs_metadata() {
+            if sort_by_metadata {
+                builder.sort_by_file_path(move |a, b| {
+                    sort.compare(&SortKey::from_path(a), &SortKey::from_path(b))
+                });
+            }
+        } else if !sort.is_none() {
+            builder.sort_by_file_name(move |a, b| {
+                sort.compare(&SortKey::from_name(a), &SortKey::from_name(b))
+            });
+        }
+
+        Ok(builder.build())
+    }
+}
@@ -0,0 +1,162 @@
+use std::fs::Metadata;
+use std::time::SystemTime;
+
+/// Limits on the metadata of the files to include. Directories always pass.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MetadataFilter {
+    /// Smallest size in bytes to include.
+    pub min_size: Option<u64>,
+    /// Largest size in bytes to include.
+    pub max_size: Option<u64>,
+    /// Only include entries modified after this time.
+    pub newer_than: Option<SystemTime>,
+    /// Only include entries modified before this time.
+    pub older_than: Option<SystemTime>,
+}
+
+impl MetadataFilter {
+    pub fn is_empty(&self) -> bool {
+        self.min_size.is_none()
+            && self.max_size.is_none()
+            && self.newer_than.is_none()
+            && self.older_than.is_none()
+    }
+
+    pub fn matches(&self, metadata: &Metadata) -> bool {
+        let size = metadata.len();
+
+        if self.min_size.is_some_and(|min| size < min)
+            || self.max_size.is_some_and(|max| size > max)
+        {
+            return false;
+        }
+
+        if self.newer_than.is_none() && self.older_than.is_none() {
+            return true;
+        }
+
+        let Ok(modified) = metadata.modified() else {
+            return false;
+        };
+
+        self.newer_than.is_none_or(|time| modified > time)
+            && self.older_than.is_none_or(|time| modified < time)
+    }
+}
+
+/// Parses a size like `512`, `10K` or `1.5G`, using binary units.
+pub fn parse_size(s: &str) -> Result<u64, String> {
+    let s = s.trim();
+
+    let split = s
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(s.len());
+
+    let (number, unit) = s.split_at(split);
+
+    let number: f64 = number.parse().map_err(|_| format!("invalid size '{s}'"))?;
+
+    let multiplier: u64 = match unit.trim().to_ascii_uppercase().as_str() {
+        "" | "B" => 1,
+        "K" | "KB" | "KIB" => 1 << 10,
+        "M" | "MB" | "MIB" => 1 << 20,
+        "G" | "GB" | "GIB" => 1 << 30,
+        "T" | "TB" | "TIB" => 1 << 40,
+        "P" | "PB" | "PIB" => 1 << 50,
+        _ => return Err(format!("invalid size unit '{unit}'")),
+    };
+
+    Ok((number * multiplier as f64) as u64)
+}
+
+/// Parses either a duration before now, like `2h` or `3days ago`, or a date like
+/// `2024-01-31` or `2024-01-31 12:00:00`, in UTC.
+pub fn parse_time(s: &str) -> Result<SystemTime, String> {
+    let duration = s.strip_suffix(" ago").unwrap_or(s);
+
+    if let Ok(duration) = humantime::parse_duration(duration) {
+        return SystemTime::now()
+            .checked_sub(duration)
+            .ok_or_else(|| format!("duration '{s}' is too long"));
+    }
+
+    let date = if s.len() == "YYYY-MM-DD".len() {
+        format!("{s} 00:00:00")
+    } else {
+        s.to_string()
+    };
+
+    humantime::parse_rfc3339_weak(&date).map_err(|_| format!("invalid duration or date '{s}'"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn parse_size_accepts_binary_units() {
+        assert_eq!(parse_size("512"), Ok(512));
+        assert_eq!(parse_size("512B"), Ok(512));
+        assert_eq!(parse_size("10K"), Ok(10 << 10));
+        assert_eq!(parse_size("10KB"), Ok(10 << 10));
+        assert_eq!(parse_size("10KiB"), Ok(10 << 10));
+        assert_eq!(parse_size("10k"), Ok(10 << 10));
+        assert_eq!(parse_size("1.5G"), Ok(3 << 29));
+        assert_eq!(parse_size("2 MiB"), Ok(2 << 20));
+        assert_eq!(parse_size("1PB"), Ok(1 << 50));
+    }
+
+    #[test]
+    fn parse_size_rejects_unknown_suffixes() {
+        for s in [
+            "", "K", "10IB", "10BB", "4KBBB", "4KIBB", "10X", "10KIBI", "-1",
+        ] {
+            assert!(parse_size(s).is_err(), "{s:?} should be rejected");
+        }
+    }
+
+    #[test]
+    fn parse_time_accepts_dates() {
+        let midnight = SystemTime::UNIX_EPOCH + Duration::from_secs(1_706_659_200);
+
+        assert_eq!(parse_time("2024-01-31"), Ok(midnight));
+        assert_eq!(
+            parse_time("2024-01-31 12:00:00"),
+            Ok(midnight + Duration::from_secs(12 * 60 * 60))
+        );
+        assert_eq!(
+            parse_time("2024-01-31T12:00:00Z"),
+            Ok(midnight + Duration::from_secs(12 * 60 * 60))
+        );
+    }
+
+    #[test]
+    fn parse_time_accepts_durations_before_now() {
+        let time = parse_time("2h").unwrap();
+        let age = SystemTime::now().duration_since(time).unwrap();
+
+        assert!(age >= Duration::from_secs(2 * 60 * 60));
+        assert!(age < Duration::from_secs(2 * 60 * 60 + 60));
+
+        let time = parse_time("30days ago").unwrap();
+        let age = SystemTime::now().duration_since(time).unwrap();
+
+        assert!(age >= Duration::from_secs(30 * 24 * 60 * 60));
+        assert!(age < Duration::from_secs(30 * 24 * 60 * 60 + 60));
+    }
+
+    #[test]
+    fn parse_time_rejects_garbage() {
+        for s in [
+            "",
+            "ago",
+            "2h ago ago",
+            "yesterday",
+            "2024-13-01",
+            "2024-01-31 25:00:00",
+        ] {
+            assert!(parse_time(s).is_err(), "{s:?} should be rejected");
+        }
+    }
+}
@@ -0,0 +1,18 @@
+//! A gitignore-aware directory tree listing.
+//!
+//! A [`WalkConfig`] describes which entries to visit, [`Tree::from_walk`]
+//! collects them into a [`Tree`], and the types in [`render`] print it.
+
+#![deny(unsafe_code)]
+
+pub mod config;
+pub mod filter;
+pub mod ndjson;
+pub mod render;
+pub mod sort;
+pub mod tree;
+
+pub use config::WalkConfig;
+pub use filter::MetadataFilter;
+pub use sort::{Collation, DirectoryOrder, Sort, SortBy};
+pub use tree::{FileType, Node, Summary, Tree};
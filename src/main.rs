@@ -1,14 +1,23 @@
 #![deny(unsafe_code)]
 
 use camino::Utf8PathBuf;
-use clap::Parser;
-use ignore::WalkBuilder;
-use owo_colors::OwoColorize;
-use ptree::{print_tree, TreeBuilder};
+use clap::{ArgAction, ArgGroup, Parser, ValueEnum};
+use lscolors::LsColors;
+use std::io::IsTerminal;
 use std::process::ExitCode;
+use std::time::SystemTime;
+use treeeee::filter::{parse_size, parse_time};
+use treeeee::render::{self, Annotations, Renderer};
+use treeeee::{ndjson, Collation, DirectoryOrder, MetadataFilter, Sort, SortBy, Tree, WalkConfig};
 
 #[derive(Debug, Parser)]
-#[command(version, about, long_about = None)]
+#[command(
+    version,
+    about,
+    long_about = None,
+    disable_help_flag = true,
+    group(ArgGroup::new("output").multiple(false))
+)]
 struct Args {
     /// Directory to display
     #[clap(default_value = ".")]
@@ -29,137 +38,379 @@ struct Args {
     /// Do not respect .gitignore files
     #[clap(short, long)]
     no_ignore: bool,
+
+    /// Only display files matching this glob, can be given multiple times
+    #[clap(short = 'P', long = "pattern", value_name = "GLOB")]
+    patterns: Vec<String>,
+
+    /// Do not display entries matching this glob, can be given multiple times
+    #[clap(short = 'I', long = "exclude", value_name = "GLOB")]
+    excludes: Vec<String>,
+
+    /// Only display files of this type, can be given multiple times
+    #[clap(short = 't', long = "type", value_name = "TYPE")]
+    types: Vec<String>,
+
+    /// Do not display files of this type, can be given multiple times
+    #[clap(short = 'T', long = "type-not", value_name = "TYPE")]
+    types_not: Vec<String>,
+
+    /// Add a file type definition, like 'name:*.ext'
+    #[clap(long = "type-add", value_name = "DEF")]
+    type_defs: Vec<String>,
+
+    /// List all known file types and exit
+    #[clap(long)]
+    type_list: bool,
+
+    /// Only display files of at least this size, like 10K or 1.5M
+    #[clap(long, value_name = "SIZE", value_parser = parse_size)]
+    min_size: Option<u64>,
+
+    /// Only display files of at most this size, like 10K or 1.5M
+    #[clap(long, value_name = "SIZE", value_parser = parse_size)]
+    max_size: Option<u64>,
+
+    /// Only display files modified within a duration like 2h, or since a date like 2024-01-31
+    #[clap(long, value_name = "WHEN", value_parser = parse_time)]
+    newer_than: Option<SystemTime>,
+
+    /// Only display files modified before a duration like 30days ago, or a date like 2024-01-31
+    #[clap(long, value_name = "WHEN", value_parser = parse_time)]
+    older_than: Option<SystemTime>,
+
+    /// Do not descend into directories with more than this many entries
+    #[clap(long, value_name = "N")]
+    filelimit: Option<usize>,
+
+    /// With --filelimit, show the first N entries of large directories instead of none
+    #[clap(long, requires = "filelimit")]
+    elide: bool,
+
+    /// Merge directories that only contain one subdirectory into a single entry
+    #[clap(long)]
+    compact: bool,
+
+    /// Do not display directories that end up empty
+    #[clap(long)]
+    prune: bool,
+
+    /// Only display directories
+    #[clap(long, conflicts_with_all = ["prune", "files_only"])]
+    dirs_only: bool,
+
+    /// Only display files, as a flat list of paths
+    #[clap(long)]
+    files_only: bool,
+
+    /// Print the size of each entry in bytes
+    #[clap(short, long)]
+    size: bool,
+
+    /// Print sizes in human readable units, implies --size
+    #[clap(short, long)]
+    human: bool,
+
+    /// Print the cumulative size of each directory, implies --size
+    #[clap(long)]
+    du: bool,
+
+    /// Append an indicator to names: / for directories, * for executables,
+    /// @ for symlinks, | for FIFOs and = for sockets
+    #[clap(short = 'F', long)]
+    classify: bool,
+
+    /// Print the type and permissions of each entry, like ls -l
+    #[clap(short = 'p', long = "perms")]
+    permissions: bool,
+
+    /// Print the owning user of each entry
+    #[clap(short, long)]
+    user: bool,
+
+    /// Print the owning group of each entry
+    #[clap(short, long)]
+    group: bool,
+
+    /// Sort entries within each directory
+    #[clap(long, value_enum, default_value_t = SortBy::Name)]
+    sort: SortBy,
+
+    /// How names are compared when sorting
+    #[clap(long, value_enum, default_value_t = Collation::Bytes)]
+    collation: Collation,
+
+    /// Reverse the sort order
+    #[clap(short, long)]
+    reverse: bool,
+
+    /// List directories before other entries
+    #[clap(long, conflicts_with = "files_first")]
+    dirs_first: bool,
+
+    /// List directories after other entries
+    #[clap(long)]
+    files_first: bool,
+
+    /// When to color the output
+    #[clap(long, value_enum, value_name = "WHEN", default_value_t = ColorChoice::Auto)]
+    color: ColorChoice,
+
+    /// Output format
+    #[clap(long, value_enum, default_value_t = Format::Tree, group = "output")]
+    format: Format,
+
+    /// Print the tree as a JSON document, same as --format json
+    #[clap(long, group = "output")]
+    json: bool,
+
+    /// Stream one JSON record per entry as it is walked, same as --format ndjson.
+    /// Options that reshape or annotate the finished tree are ignored
+    #[clap(long, group = "output")]
+    ndjson: bool,
+
+    /// Print the tree as XML compatible with GNU tree's -X, same as --format xml
+    #[clap(short = 'X', long, group = "output")]
+    xml: bool,
+
+    /// Print the tree as a standalone HTML page, same as --format html
+    #[clap(long, group = "output")]
+    html: bool,
+
+    /// URL prepended to file links in HTML output
+    #[clap(long, value_name = "URL")]
+    base_url: Option<String>,
+
+    /// Print the tree as a nested Markdown list, same as --format markdown
+    #[clap(long, group = "output")]
+    markdown: bool,
+
+    /// Format names in Markdown output as inline code
+    #[clap(long)]
+    markdown_code: bool,
+
+    /// Link files in Markdown output relative to the root directory
+    #[clap(long)]
+    markdown_links: bool,
+
+    /// Print help
+    #[clap(long, action = ArgAction::Help)]
+    help: Option<bool>,
+}
+
+impl Args {
+    /// Resolves the output format from `--format` and its shorthand flags.
+    fn format(&self) -> Format {
+        if self.json {
+            Format::Json
+        } else if self.ndjson {
+            Format::Ndjson
+        } else if self.xml {
+            Format::Xml
+        } else if self.html {
+            Format::Html
+        } else if self.markdown {
+            Format::Markdown
+        } else {
+            self.format
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum ColorChoice {
+    /// Color when printing to a terminal, unless NO_COLOR is set or CLICOLOR_FORCE is
+    Auto,
+    /// Always color
+    Always,
+    /// Never color
+    Never,
+}
+
+impl ColorChoice {
+    fn enabled(self) -> bool {
+        let env = |name| std::env::var_os(name).filter(|value| !value.is_empty());
+
+        match self {
+            Self::Always => true,
+            Self::Never => false,
+            Self::Auto if env("NO_COLOR").is_some() => false,
+            Self::Auto if env("CLICOLOR_FORCE").is_some_and(|value| value != "0") => true,
+            Self::Auto => std::io::stdout().is_terminal(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Format {
+    /// Box-drawing tree
+    Tree,
+    /// Nested JSON document
+    Json,
+    /// One JSON record per line, streamed during the walk
+    Ndjson,
+    /// XML compatible with GNU tree
+    Xml,
+    /// Standalone HTML page
+    Html,
+    /// Nested Markdown list
+    Markdown,
+    /// Graphviz DOT graph
+    Dot,
+    /// Mermaid flowchart
+    Mermaid,
 }
 
 fn main() -> ExitCode {
     let args = Args::parse();
 
-    let walker = WalkBuilder::new(&args.dir)
-        .ignore(!args.no_ignore)
-        .git_ignore(!args.no_ignore)
-        .git_global(!args.no_ignore)
-        .max_depth(args.depth)
-        .hidden(!args.hidden)
-        .sort_by_file_name(|a, b| a.cmp(b))
-        .skip_stdout(true)
-        .build();
-
-    let mut tree = TreeBuilder::new(args.dir.to_string());
-
-    let mut current_depth: usize = 1;
-
-    let mut files_count: usize = 0;
-    let mut dirs_count: usize = 0;
-    let mut symlink_count: usize = 0;
-
-    for entry in walker.skip(1) {
-        match entry {
-            Ok(entry) => {
-                let entry_depth = entry.depth();
-
-                if entry_depth < current_depth {
-                    for _ in 0..(current_depth - entry_depth) {
-                        tree.end_child();
-                    }
-
-                    current_depth = entry_depth;
-                }
-
-                match entry.file_type() {
-                    Some(file_type) => {
-                        let file_type = file_type.into();
-
-                        let file_name = entry.file_name().to_string_lossy();
-
-                        match file_type {
-                            FileType::Directory => {
-                                tree.begin_child(format!("{}/", file_name.green()));
-
-                                current_depth += 1;
-
-                                dirs_count += 1;
-                            }
-                            FileType::File => {
-                                tree.add_empty_child(file_name.into());
-
-                                files_count += 1;
-                            }
-                            FileType::Symlink => {
-                                tree.add_empty_child(format!(
-                                    "{} -> {}",
-                                    file_name.blue(),
-                                    match entry.path().read_link() {
-                                        Ok(s) => s,
-                                        Err(err) => {
-                                            if !args.ignore_errors {
-                                                eprintln!("{}", err);
-                                            }
-                                            continue;
-                                        }
-                                    }
-                                    .to_string_lossy()
-                                    .cyan()
-                                ));
-
-                                files_count += 1;
-                                symlink_count += 1;
-                            }
-                            FileType::Other => {
-                                tree.add_empty_child(format!("{}", file_name.red()));
-
-                                files_count += 1;
-                            }
-                        }
-                    }
-                    None => continue,
-                }
-            }
-            Err(err) => {
-                if !args.ignore_errors {
-                    eprintln!("{}", err);
-                }
-            }
+    let config = WalkConfig {
+        max_depth: args.depth,
+        hidden: args.hidden,
+        respect_ignore: !args.no_ignore,
+        patterns: args.patterns.clone(),
+        excludes: args.excludes.clone(),
+        types: args.types.clone(),
+        types_not: args.types_not.clone(),
+        type_defs: args.type_defs.clone(),
+        filter: MetadataFilter {
+            min_size: args.min_size,
+            max_size: args.max_size,
+            newer_than: args.newer_than,
+            older_than: args.older_than,
+        },
+        dirs_only: args.dirs_only,
+        sort: Sort {
+            by: args.sort,
+            reverse: args.reverse,
+            collation: args.collation,
+            directories: if args.dirs_first {
+                DirectoryOrder::First
+            } else if args.files_first {
+                DirectoryOrder::Last
+            } else {
+                DirectoryOrder::Mixed
+            },
+        },
+        ..WalkConfig::new(args.dir.clone())
+    };
+
+    let format = args.format();
+
+    let mut stdout = std::io::stdout().lock();
+
+    let written = if args.type_list {
+        print_type_list(&config, &mut stdout)
+    } else if format == Format::Ndjson {
+        ndjson::stream(&config, args.ignore_errors, &mut stdout)
+    } else {
+        render_tree(&args, &config, format, &mut stdout)
+    };
+
+    match written {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) if err.kind() == std::io::ErrorKind::BrokenPipe => ExitCode::FAILURE,
+        Err(err) => {
+            eprintln!("{}", err);
+
+            ExitCode::FAILURE
         }
     }
+}
+
+fn print_type_list(config: &WalkConfig, out: &mut dyn std::io::Write) -> std::io::Result<()> {
+    let definitions = config.type_definitions().map_err(std::io::Error::other)?;
+
+    for definition in definitions {
+        writeln!(
+            out,
+            "{}: {}",
+            definition.name(),
+            definition.globs().join(", ")
+        )?;
+    }
+
+    Ok(())
+}
 
-    print_tree(&tree.build())
-        .map_err(|err| {
+fn render_tree(
+    args: &Args,
+    config: &WalkConfig,
+    format: Format,
+    out: &mut dyn std::io::Write,
+) -> std::io::Result<()> {
+    let mut tree = Tree::from_walk(config, |err| {
+        if !args.ignore_errors {
             eprintln!("{}", err);
-        })
-        .ok();
-
-    println!(
-        "\n{} directories, {} files{}",
-        dirs_count,
-        files_count,
-        if symlink_count > 0 {
-            format!(", {} symlinks", symlink_count)
-        } else {
-            "".to_string()
         }
-    );
+    })
+    .map_err(std::io::Error::other)?;
 
-    ExitCode::SUCCESS
-}
+    if args.prune {
+        tree.prune();
+    }
 
-#[derive(Debug)]
-enum FileType {
-    Directory,
-    File,
-    Symlink,
-    Other,
-}
+    if args.files_only {
+        tree.flatten_files();
 
-impl From<std::fs::FileType> for FileType {
-    fn from(file_type: std::fs::FileType) -> Self {
-        if file_type.is_dir() {
-            Self::Directory
-        } else if file_type.is_file() {
-            Self::File
-        } else if file_type.is_symlink() {
-            Self::Symlink
-        } else {
-            Self::Other
+        // The walk only ordered entries within each directory.
+        tree.sort(&config.sort);
+    }
+
+    if args.du {
+        tree.accumulate_sizes();
+
+        if args.sort == SortBy::Size {
+            tree.sort(&config.sort);
         }
     }
+
+    if let Some(limit) = args.filelimit {
+        tree.limit_entries(limit, args.elide);
+    }
+
+    if args.compact {
+        tree.compact();
+    }
+
+    let annotations = Annotations {
+        size: args.size || args.human || args.du,
+        human: args.human,
+        classify: args.classify,
+        permissions: args.permissions,
+        user: args.user,
+        group: args.group,
+    };
+
+    let renderer: Box<dyn Renderer> = match format {
+        Format::Tree => Box::new(render::Text {
+            annotations,
+            colors: args
+                .color
+                .enabled()
+                .then(|| LsColors::from_env().unwrap_or_default()),
+        }),
+        Format::Json => Box::new(render::Json),
+        Format::Ndjson => unreachable!("streamed without building a tree"),
+        Format::Xml => Box::new(render::Xml {
+            size: annotations.size,
+        }),
+        Format::Html => Box::new(render::Html {
+            base_url: args.base_url.clone().unwrap_or_default(),
+            annotations,
+        }),
+        Format::Markdown => Box::new(render::Markdown {
+            code: args.markdown_code,
+            links: args.markdown_links,
+            annotations,
+        }),
+        Format::Dot => Box::new(render::Dot {
+            dir: args.dir.clone(),
+        }),
+        Format::Mermaid => Box::new(render::Mermaid {
+            dir: args.dir.clone(),
+        }),
+    };
+
+    renderer.render(&tree, out)
 }
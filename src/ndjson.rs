@@ -0,0 +1,70 @@
+use crate::config::WalkConfig;
+use crate::tree::FileType;
+use serde::Serialize;
+use std::io::Write;
+
+/// A single line written by `--ndjson`.
+#[derive(Debug, Serialize)]
+struct Record {
+    path: Option<String>,
+    depth: Option<usize>,
+    #[serde(rename = "type")]
+    file_type: Option<FileType>,
+    target: Option<String>,
+    error: Option<String>,
+}
+
+/// Writes one record per entry as soon as it is walked, without building a [`Tree`].
+///
+/// [`Tree`]: crate::Tree
+pub fn stream(
+    config: &WalkConfig,
+    ignore_errors: bool,
+    out: &mut dyn Write,
+) -> std::io::Result<()> {
+    let walker = config.build().map_err(std::io::Error::other)?;
+
+    for entry in walker.skip(1) {
+        let record = match entry {
+            Ok(entry) => {
+                let Some(file_type) = entry.file_type().map(FileType::from) else {
+                    continue;
+                };
+
+                let mut record = Record {
+                    path: Some(entry.path().to_string_lossy().into()),
+                    depth: Some(entry.depth()),
+                    file_type: Some(file_type),
+                    target: None,
+                    error: None,
+                };
+
+                if let FileType::Symlink = file_type {
+                    match entry.path().read_link() {
+                        Ok(target) => record.target = Some(target.to_string_lossy().into()),
+                        Err(err) => record.error = Some(err.to_string()),
+                    }
+                }
+
+                record
+            }
+            Err(err) => Record {
+                path: None,
+                depth: None,
+                file_type: None,
+                target: None,
+                error: Some(err.to_string()),
+            },
+        };
+
+        if record.error.is_some() && ignore_errors {
+            continue;
+        }
+
+        serde_json::to_writer(&mut *out, &record)?;
+
+        writeln!(out)?;
+    }
+
+    Ok(())
+}
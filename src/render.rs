@@ -0,0 +1,296 @@
+mod dot;
+mod graph;
+mod html;
+mod json;
+mod markdown;
+mod mermaid;
+mod text;
+mod xml;
+
+pub use dot::Dot;
+pub use html::Html;
+pub use json::Json;
+pub use markdown::Markdown;
+pub use mermaid::Mermaid;
+pub use text::Text;
+pub use xml::Xml;
+
+use crate::tree::{FileType, Node, Tree};
+use std::io::Write;
+
+/// An output format for a fully walked [`Tree`].
+pub trait Renderer {
+    fn render(&self, tree: &Tree, out: &mut dyn Write) -> std::io::Result<()>;
+}
+
+/// Extra per-entry details shown around each name.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Annotations {
+    /// Show the size of each entry.
+    pub size: bool,
+    /// Show sizes in human readable units.
+    pub human: bool,
+    /// Mark each name with a character indicating its type, like `ls -F`.
+    pub classify: bool,
+    /// Show the type and permissions of each entry, like `ls -l`.
+    pub permissions: bool,
+    /// Show the owning user of each entry.
+    pub user: bool,
+    /// Show the owning group of each entry.
+    pub group: bool,
+}
+
+impl Annotations {
+    /// Returns the bracketed details for `node`, followed by a space, or nothing.
+    fn prefix(&self, node: &Node) -> String {
+        let mut details = Vec::new();
+
+        if self.permissions {
+            details.push(mode_string(node));
+        }
+
+        if self.user {
+            details.push(format!("{:<8}", user_name(node.uid)));
+        }
+
+        if self.group {
+            details.push(format!("{:<8}", group_name(node.gid)));
+        }
+
+        if self.size && self.human {
+            details.push(format!("{:>4}", human_size(node.size)));
+        } else if self.size {
+            details.push(format!("{:>11}", node.size));
+        }
+
+        if details.is_empty() {
+            String::new()
+        } else {
+            format!("[{}] ", details.join(" "))
+        }
+    }
+
+    /// Returns the indicator appended to the name of `node`.
+    ///
+    /// Directories are always marked, everything else only when classifying.
+    fn suffix(&self, node: &Node) -> &'static str {
+        match node.file_type {
+            FileType::Directory => "/",
+            _ if !self.classify => "",
+            FileType::Symlink => "@",
+            FileType::Fifo => "|",
+            FileType::Socket => "=",
+            FileType::File if node.is_executable() => "*",
+            _ => "",
+        }
+    }
+}
+
+/// Formats the type and permission bits of `node` like `ls -l`, e.g. `drwxr-xr-x`.
+fn mode_string(node: &Node) -> String {
+    let mode = node.mode;
+
+    let kind = match node.file_type {
+        FileType::Directory => 'd',
+        FileType::File => '-',
+        FileType::Symlink => 'l',
+        FileType::BlockDevice => 'b',
+        FileType::CharDevice => 'c',
+        FileType::Fifo => 'p',
+        FileType::Socket => 's',
+        FileType::Other => '?',
+    };
+
+    // Read, write and execute bits for user, group and other, together with the
+    // setuid, setgid and sticky bit that replaces each execute character.
+    let classes = [
+        (0o400, 0o200, 0o100, 0o4000, 's'),
+        (0o040, 0o020, 0o010, 0o2000, 's'),
+        (0o004, 0o002, 0o001, 0o1000, 't'),
+    ];
+
+    let mut s = String::with_capacity(10);
+
+    s.push(kind);
+
+    for (read, write, execute, special, special_char) in classes {
+        s.push(if mode & read != 0 { 'r' } else { '-' });
+        s.push(if mode & write != 0 { 'w' } else { '-' });
+        s.push(match (mode & execute != 0, mode & special != 0) {
+            (true, true) => special_char,
+            (false, true) => special_char.to_ascii_uppercase(),
+            (true, false) => 'x',
+            (false, false) => '-',
+        });
+    }
+
+    s
+}
+
+#[cfg(unix)]
+thread_local! {
+    static USERS: uzers::UsersCache = uzers::UsersCache::new();
+}
+
+/// Resolves a user id to a name, falling back to the number itself.
+fn user_name(uid: Option<u32>) -> String {
+    let Some(uid) = uid else {
+        return "?".to_string();
+    };
+
+    #[cfg(unix)]
+    {
+        use uzers::Users;
+
+        let user = USERS.with(|users| users.get_user_by_uid(uid));
+
+        if let Some(user) = user {
+            return user.name().to_string_lossy().into_owned();
+        }
+    }
+
+    uid.to_string()
+}
+
+/// Resolves a group id to a name, falling back to the number itself.
+fn group_name(gid: Option<u32>) -> String {
+    let Some(gid) = gid else {
+        return "?".to_string();
+    };
+
+    #[cfg(unix)]
+    {
+        use uzers::Groups;
+
+        let group = USERS.with(|users| users.get_group_by_gid(gid));
+
+        if let Some(group) = group {
+            return group.name().to_string_lossy().into_owned();
+        }
+    }
+
+    gid.to_string()
+}
+
+/// Formats a byte count with binary units, like GNU tree's `-h`.
+pub fn human_size(size: u64) -> String {
+    const UNITS: [&str; 6] = ["K", "M", "G", "T", "P", "E"];
+
+    if size < 1024 {
+        return size.to_string();
+    }
+
+    let mut value = size as f64 / 1024.0;
+    let mut unit = 0;
+
+    // Thresholds apply to the rounded value, so that it never takes more than
+    // three digits, or two with a decimal.
+    loop {
+        if value < 9.95 {
+            return format!("{:.1}{}", value, UNITS[unit]);
+        }
+
+        if value.round() < 1024.0 || unit == UNITS.len() - 1 {
+            return format!("{}{}", value.round(), UNITS[unit]);
+        }
+
+        value /= 1024.0;
+        unit += 1;
+    }
+}
+
+/// Describes the children of `node` left out by [`Tree::limit_entries`], if any.
+fn omitted_label(node: &Node) -> Option<String> {
+    match node.omitted {
+        0 => None,
+        n if node.children.is_empty() => Some(format!("… {n} entries")),
+        n => Some(format!("… and {n} more entries")),
+    }
+}
+
+/// Escapes a string for use in XML text or a double quoted attribute.
+fn xml_escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+/// Appends `name`, which may contain several path segments, to the link `href`.
+fn join_href(href: &str, name: &str) -> String {
+    let name = name
+        .split('/')
+        .map(url_escape)
+        .collect::<Vec<_>>()
+        .join("/");
+
+    if href.is_empty() || href.ends_with('/') {
+        format!("{href}{name}")
+    } else {
+        format!("{href}/{name}")
+    }
+}
+
+/// Percent-encodes a path segment for use in a URL.
+fn url_escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+
+    for byte in s.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                escaped.push(byte as char)
+            }
+            byte => escaped.push_str(&format!("%{byte:02X}")),
+        }
+    }
+
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn human_size_keeps_bytes_exact() {
+        assert_eq!(human_size(0), "0");
+        assert_eq!(human_size(1023), "1023");
+    }
+
+    #[test]
+    fn human_size_uses_one_decimal_below_ten() {
+        assert_eq!(human_size(1024), "1.0K");
+        assert_eq!(human_size(1536), "1.5K");
+        assert_eq!(human_size(10188), "9.9K");
+    }
+
+    #[test]
+    fn human_size_rounds_before_choosing_the_format() {
+        assert_eq!(human_size(10200), "10K");
+        assert_eq!(human_size(1_048_575), "1.0M");
+        assert_eq!(human_size(1023 * 1024), "1023K");
+        assert_eq!(human_size(10 << 20), "10M");
+    }
+
+    #[test]
+    fn human_size_fits_four_columns() {
+        for size in (0..u64::BITS).flat_map(|shift| [(1 << shift) - 1, 1 << shift, 10200 << shift])
+        {
+            assert!(
+                human_size(size).len() <= 4,
+                "{size} is {}",
+                human_size(size)
+            );
+        }
+    }
+}
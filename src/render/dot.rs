@@ -0,0 +1,56 @@
+use super::graph::Graph;
+use super::Renderer;
+use crate::tree::{FileType, Tree};
+use camino::Utf8PathBuf;
+use std::io::Write;
+
+/// A Graphviz DOT digraph.
+pub struct Dot {
+    /// Directory the tree was walked from, used to resolve symlinks.
+    pub dir: Utf8PathBuf,
+}
+
+impl Renderer for Dot {
+    fn render(&self, tree: &Tree, out: &mut dyn Write) -> std::io::Result<()> {
+        let graph = Graph::new(&tree.root, &self.dir);
+
+        writeln!(out, "// {}", tree.summary)?;
+        writeln!(out, "digraph tree {{")?;
+        writeln!(out, "  rankdir=LR;")?;
+        writeln!(out, "  node [shape=box, fontname=monospace];")?;
+
+        for (id, node) in graph.nodes.iter().enumerate() {
+            let (label, color) = match node.file_type {
+                FileType::Directory => (format!("{}/", node.name), "green"),
+                FileType::File => (node.name.clone(), "black"),
+                FileType::Symlink => (node.name.clone(), "blue"),
+                FileType::BlockDevice | FileType::CharDevice | FileType::Fifo => {
+                    (node.name.clone(), "orange")
+                }
+                FileType::Socket => (node.name.clone(), "magenta"),
+                FileType::Other => (node.name.clone(), "red"),
+            };
+
+            writeln!(
+                out,
+                r#"  n{id} [label="{}", fontcolor={color}];"#,
+                escape(&label)
+            )?;
+        }
+
+        for (from, to) in &graph.edges {
+            writeln!(out, "  n{from} -> n{to};")?;
+        }
+
+        for (from, to) in &graph.links {
+            writeln!(out, "  n{from} -> n{to} [style=dashed, color=cyan];")?;
+        }
+
+        writeln!(out, "}}")
+    }
+}
+
+/// Escapes a string for use in a double quoted DOT identifier.
+fn escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
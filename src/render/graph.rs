@@ -0,0 +1,76 @@
+use crate::tree::{FileType, Node};
+use camino::Utf8Path;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+/// The nodes and edges of the walked hierarchy, numbered in walk order.
+pub struct Graph<'a> {
+    pub nodes: Vec<&'a Node>,
+    /// Parent to child edges.
+    pub edges: Vec<(usize, usize)>,
+    /// Symlink to target edges, for targets that resolve inside the tree.
+    pub links: Vec<(usize, usize)>,
+}
+
+impl<'a> Graph<'a> {
+    /// Numbers the nodes below `root`, which was walked from `dir`.
+    pub fn new(root: &'a Node, dir: &Utf8Path) -> Self {
+        let mut graph = Self {
+            nodes: Vec::new(),
+            edges: Vec::new(),
+            links: Vec::new(),
+        };
+
+        let mut ids = HashMap::new();
+        let mut symlinks = Vec::new();
+
+        graph.add(root, PathBuf::new(), &mut ids, &mut symlinks);
+
+        if let Ok(canonical_root) = dir.canonicalize() {
+            for (id, path) in symlinks {
+                let target = dir
+                    .as_std_path()
+                    .join(&path)
+                    .canonicalize()
+                    .ok()
+                    .and_then(|target| {
+                        target
+                            .strip_prefix(&canonical_root)
+                            .ok()
+                            .and_then(|target| ids.get(target))
+                            .copied()
+                    });
+
+                if let Some(target) = target {
+                    graph.links.push((id, target));
+                }
+            }
+        }
+
+        graph
+    }
+
+    fn add(
+        &mut self,
+        node: &'a Node,
+        path: PathBuf,
+        ids: &mut HashMap<PathBuf, usize>,
+        symlinks: &mut Vec<(usize, PathBuf)>,
+    ) {
+        let id = self.nodes.len();
+
+        self.nodes.push(node);
+
+        for child in &node.children {
+            self.edges.push((id, self.nodes.len()));
+
+            self.add(child, path.join(&child.name), ids, symlinks);
+        }
+
+        if node.file_type == FileType::Symlink {
+            symlinks.push((id, path.clone()));
+        }
+
+        ids.insert(path, id);
+    }
+}
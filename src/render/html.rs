@@ -0,0 +1,99 @@
+use super::{join_href, omitted_label, xml_escape, Annotations, Renderer};
+use crate::tree::{FileType, Node, Tree};
+use std::io::Write;
+
+/// A standalone HTML page with collapsible directories.
+pub struct Html {
+    /// URL that file links are relative to.
+    pub base_url: String,
+    pub annotations: Annotations,
+}
+
+const STYLE: &str = r#"
+body { font-family: monospace; }
+ul { list-style: none; margin: 0; padding-left: 1.5em; }
+summary { cursor: pointer; }
+a { color: inherit; text-decoration: none; }
+a:hover { text-decoration: underline; }
+.directory { color: green; }
+.symlink { color: blue; }
+.target { color: darkcyan; }
+.block_device, .char_device, .fifo { color: darkorange; }
+.socket { color: magenta; }
+.other { color: red; }
+.omitted { color: gray; }
+"#;
+
+impl Renderer for Html {
+    fn render(&self, tree: &Tree, out: &mut dyn Write) -> std::io::Result<()> {
+        let title = xml_escape(&tree.root.name);
+
+        let mut base_url = self.base_url.clone();
+
+        if !base_url.is_empty() && !base_url.ends_with('/') {
+            base_url.push('/');
+        }
+
+        writeln!(out, "<!DOCTYPE html>")?;
+        writeln!(out, r#"<html lang="en">"#)?;
+        writeln!(out, "<head>")?;
+        writeln!(out, r#"<meta charset="utf-8">"#)?;
+        writeln!(out, "<title>{title}</title>")?;
+        writeln!(out, "<style>")?;
+        writeln!(out, "{}", STYLE.trim())?;
+        writeln!(out, "</style>")?;
+        writeln!(out, "</head>")?;
+        writeln!(out, "<body>")?;
+        writeln!(out, "<ul>")?;
+
+        self.write_node(out, &tree.root, &base_url)?;
+
+        writeln!(out, "</ul>")?;
+        writeln!(out, "<p>{}</p>", tree.summary)?;
+        writeln!(out, "</body>")?;
+        writeln!(out, "</html>")
+    }
+}
+
+impl Html {
+    /// Writes `node` as a list item, where `href` is the link to `node` itself.
+    fn write_node(&self, out: &mut dyn Write, node: &Node, href: &str) -> std::io::Result<()> {
+        let name = xml_escape(&node.name);
+        let prefix = xml_escape(&self.annotations.prefix(node));
+        let suffix = self.annotations.suffix(node);
+
+        match node.file_type {
+            FileType::Directory => {
+                writeln!(out, "<li><details open>")?;
+                writeln!(
+                    out,
+                    r#"<summary>{prefix}<span class="directory">{name}{suffix}</span></summary>"#
+                )?;
+                writeln!(out, "<ul>")?;
+
+                for child in &node.children {
+                    self.write_node(out, child, &join_href(href, &child.name))?;
+                }
+
+                if let Some(label) = omitted_label(node) {
+                    writeln!(out, r#"<li class="omitted">{}</li>"#, xml_escape(&label))?;
+                }
+
+                writeln!(out, "</ul>")?;
+                writeln!(out, "</details></li>")
+            }
+            FileType::Symlink => writeln!(
+                out,
+                r#"<li>{prefix}<a class="symlink" href="{}">{name}{suffix}</a> -&gt; <span class="target">{}</span></li>"#,
+                xml_escape(href),
+                xml_escape(node.target.as_deref().unwrap_or_default())
+            ),
+            file_type => writeln!(
+                out,
+                r#"<li>{prefix}<a class="{}" href="{}">{name}{suffix}</a></li>"#,
+                file_type.as_str(),
+                xml_escape(href)
+            ),
+        }
+    }
+}
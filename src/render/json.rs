@@ -0,0 +1,14 @@
+use super::Renderer;
+use crate::tree::Tree;
+use std::io::Write;
+
+/// A single pretty-printed JSON document.
+pub struct Json;
+
+impl Renderer for Json {
+    fn render(&self, tree: &Tree, out: &mut dyn Write) -> std::io::Result<()> {
+        serde_json::to_writer_pretty(&mut *out, tree)?;
+
+        writeln!(out)
+    }
+}
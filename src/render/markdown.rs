@@ -0,0 +1,144 @@
+use super::{join_href, omitted_label, Annotations, Renderer};
+use crate::tree::{FileType, Node, Tree};
+use std::io::Write;
+
+/// A nested Markdown bullet list.
+pub struct Markdown {
+    /// Format names as inline code.
+    pub code: bool,
+    /// Link files relative to the root directory.
+    pub links: bool,
+    pub annotations: Annotations,
+}
+
+impl Renderer for Markdown {
+    fn render(&self, tree: &Tree, out: &mut dyn Write) -> std::io::Result<()> {
+        self.write_node(out, &tree.root, "", 0)?;
+
+        writeln!(out, "\n{}", tree.summary)
+    }
+}
+
+impl Markdown {
+    fn write_node(
+        &self,
+        out: &mut dyn Write,
+        node: &Node,
+        href: &str,
+        depth: usize,
+    ) -> std::io::Result<()> {
+        let indent = "  ".repeat(depth);
+        let prefix = escape(&self.annotations.prefix(node));
+
+        let suffix = self.annotations.suffix(node);
+
+        let mut name = self.format_name(&format!("{}{suffix}", node.name));
+
+        if self.links && node.file_type != FileType::Directory {
+            name = format!("[{name}]({href})");
+        }
+
+        match node.file_type {
+            FileType::Directory => {
+                writeln!(out, "{indent}- {prefix}{name}")?;
+
+                for child in &node.children {
+                    let href = join_href(href, &child.name);
+
+                    self.write_node(out, child, &href, depth + 1)?;
+                }
+
+                if let Some(label) = omitted_label(node) {
+                    writeln!(out, "{indent}  - {}", escape(&label))?;
+                }
+
+                Ok(())
+            }
+            FileType::Symlink => writeln!(
+                out,
+                "{indent}- {prefix}{name} -> {}",
+                self.format_name(node.target.as_deref().unwrap_or_default())
+            ),
+            _ => writeln!(out, "{indent}- {prefix}{name}"),
+        }
+    }
+
+    fn format_name(&self, name: &str) -> String {
+        if self.code {
+            code_span(name)
+        } else {
+            escape(name)
+        }
+    }
+}
+
+/// Escapes characters that Markdown would otherwise interpret.
+fn escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+
+    let marker = list_marker(s);
+
+    for (i, c) in s.char_indices() {
+        if Some(i) == marker
+            || matches!(
+                c,
+                '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '(' | ')' | '#' | '!' | '|'
+            )
+        {
+            escaped.push('\\');
+        }
+
+        escaped.push(c);
+    }
+
+    escaped
+}
+
+/// Returns the index of the character that would make `s` start a list, like
+/// the `-` in `- x` or the `.` in `1. intro`.
+fn list_marker(s: &str) -> Option<usize> {
+    let digits = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+
+    let index = match s[digits..].chars().next()? {
+        '+' | '-' if digits == 0 => 0,
+        '.' | ')' if (1..=9).contains(&digits) => digits,
+        _ => return None,
+    };
+
+    s[index + 1..]
+        .chars()
+        .next()
+        .is_none_or(char::is_whitespace)
+        .then_some(index)
+}
+
+/// Wraps a string in a Markdown code span, using a longer fence if it contains backticks.
+fn code_span(s: &str) -> String {
+    if s.contains('`') {
+        format!("`` {s} ``")
+    } else {
+        format!("`{s}`")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escape_leading_list_markers() {
+        assert_eq!(escape("+ plus"), r"\+ plus");
+        assert_eq!(escape("- x"), r"\- x");
+        assert_eq!(escape("-"), r"\-");
+        assert_eq!(escape("1. intro"), r"1\. intro");
+        assert_eq!(escape("2) two"), r"2\) two");
+    }
+
+    #[test]
+    fn escape_leaves_other_leading_characters() {
+        assert_eq!(escape("-flag"), "-flag");
+        assert_eq!(escape("x - y"), "x - y");
+        assert_eq!(escape("12.5"), "12.5");
+        assert_eq!(escape("1234567890. x"), "1234567890. x");
+    }
+}
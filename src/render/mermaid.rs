@@ -0,0 +1,56 @@
+use super::graph::Graph;
+use super::Renderer;
+use crate::tree::{FileType, Tree};
+use camino::Utf8PathBuf;
+use std::io::Write;
+
+/// A Mermaid flowchart.
+pub struct Mermaid {
+    /// Directory the tree was walked from, used to resolve symlinks.
+    pub dir: Utf8PathBuf,
+}
+
+impl Renderer for Mermaid {
+    fn render(&self, tree: &Tree, out: &mut dyn Write) -> std::io::Result<()> {
+        let graph = Graph::new(&tree.root, &self.dir);
+
+        writeln!(out, "%% {}", tree.summary)?;
+        writeln!(out, "flowchart LR")?;
+
+        for (id, node) in graph.nodes.iter().enumerate() {
+            let label = match node.file_type {
+                FileType::Directory => format!("{}/", node.name),
+                _ => node.name.clone(),
+            };
+
+            writeln!(
+                out,
+                r#"  n{id}["{}"]:::{}"#,
+                escape(&label),
+                node.file_type.as_str()
+            )?;
+        }
+
+        for (from, to) in &graph.edges {
+            writeln!(out, "  n{from} --> n{to}")?;
+        }
+
+        for (from, to) in &graph.links {
+            writeln!(out, "  n{from} -.-> n{to}")?;
+        }
+
+        writeln!(out, "  classDef directory color:green")?;
+        writeln!(out, "  classDef file color:black")?;
+        writeln!(out, "  classDef symlink color:blue")?;
+        writeln!(out, "  classDef block_device color:orange")?;
+        writeln!(out, "  classDef char_device color:orange")?;
+        writeln!(out, "  classDef fifo color:orange")?;
+        writeln!(out, "  classDef socket color:magenta")?;
+        writeln!(out, "  classDef other color:red")
+    }
+}
+
+/// Escapes a string for use in a quoted Mermaid label.
+fn escape(s: &str) -> String {
+    s.replace('#', "#35;").replace('"', "#quot;")
+}
@@ -0,0 +1,84 @@
+use super::{omitted_label, Annotations, Renderer};
+use crate::tree::{FileType, Node, Tree};
+use lscolors::LsColors;
+use owo_colors::OwoColorize;
+use ptree::{write_tree, TreeBuilder};
+use std::io::Write;
+use std::path::Path;
+
+/// The default box-drawing tree, rendered with `ptree`.
+pub struct Text {
+    pub annotations: Annotations,
+    /// Colors names with ANSI escape codes, or `None` to print them plain.
+    pub colors: Option<LsColors>,
+}
+
+impl Renderer for Text {
+    fn render(&self, tree: &Tree, out: &mut dyn Write) -> std::io::Result<()> {
+        let mut builder = TreeBuilder::new(tree.root.name.clone());
+
+        let root = Path::new(&tree.root.name);
+
+        for child in &tree.root.children {
+            self.add_to_tree(&mut builder, child, &root.join(&child.name));
+        }
+
+        if let Some(label) = omitted_label(&tree.root) {
+            builder.add_empty_child(label);
+        }
+
+        write_tree(&builder.build(), &mut *out)?;
+
+        writeln!(out, "\n{}", tree.summary)
+    }
+}
+
+impl Text {
+    /// Adds `node`, which lives at `path`, to `tree`.
+    fn add_to_tree(&self, tree: &mut TreeBuilder, node: &Node, path: &Path) {
+        let prefix = self.annotations.prefix(node);
+        let suffix = self.annotations.suffix(node);
+        let name = self.paint(&node.name, path);
+
+        match node.file_type {
+            FileType::Directory => {
+                tree.begin_child(format!("{prefix}{name}{suffix}"));
+
+                for child in &node.children {
+                    self.add_to_tree(tree, child, &path.join(&child.name));
+                }
+
+                if let Some(label) = omitted_label(node) {
+                    tree.add_empty_child(label);
+                }
+
+                tree.end_child();
+            }
+            FileType::Symlink => {
+                let target = node.target.as_deref().unwrap_or_default();
+                let target_path = path.parent().unwrap_or(path).join(target);
+
+                tree.add_empty_child(format!(
+                    "{prefix}{name}{suffix} -> {}",
+                    self.paint(target, &target_path)
+                ));
+            }
+            _ => {
+                tree.add_empty_child(format!("{prefix}{name}{suffix}"));
+            }
+        }
+    }
+
+    /// Colors `s` the way `ls` would color the entry at `path`.
+    fn paint(&self, s: &str, path: &Path) -> String {
+        let style = self
+            .colors
+            .as_ref()
+            .and_then(|colors| colors.style_for_path(path));
+
+        match style {
+            Some(style) => s.style(style.to_owo_colors_style()).to_string(),
+            None => s.to_string(),
+        }
+    }
+}
@@ -0,0 +1,79 @@
+use super::{xml_escape, Renderer};
+use crate::tree::{FileType, Node, Tree};
+use std::io::Write;
+
+/// XML following the schema of GNU tree's `-X` output.
+pub struct Xml {
+    /// Add a `size` attribute to every element.
+    pub size: bool,
+}
+
+impl Renderer for Xml {
+    fn render(&self, tree: &Tree, out: &mut dyn Write) -> std::io::Result<()> {
+        writeln!(out, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+        writeln!(out, "<tree>")?;
+
+        self.write_node(out, &tree.root, 1)?;
+
+        writeln!(out, "  <report>")?;
+
+        if let Some(directories) = tree.summary.directories {
+            writeln!(out, "    <directories>{directories}</directories>")?;
+        }
+
+        if let Some(files) = tree.summary.files {
+            writeln!(out, "    <files>{files}</files>")?;
+        }
+
+        writeln!(out, "  </report>")?;
+        writeln!(out, "</tree>")
+    }
+}
+
+impl Xml {
+    fn write_node(&self, out: &mut dyn Write, node: &Node, depth: usize) -> std::io::Result<()> {
+        let indent = "  ".repeat(depth);
+        let name = xml_escape(&node.name);
+
+        let attrs = if self.size {
+            format!(r#" size="{}""#, node.size)
+        } else {
+            String::new()
+        };
+
+        match node.file_type {
+            FileType::Directory => {
+                writeln!(out, r#"{indent}<directory name="{name}"{attrs}>"#)?;
+
+                for child in &node.children {
+                    self.write_node(out, child, depth + 1)?;
+                }
+
+                if node.omitted > 0 {
+                    writeln!(out, r#"{indent}  <omitted count="{}"/>"#, node.omitted)?;
+                }
+
+                writeln!(out, "{indent}</directory>")
+            }
+            FileType::Symlink => {
+                let target = xml_escape(node.target.as_deref().unwrap_or_default());
+
+                writeln!(
+                    out,
+                    r#"{indent}<link name="{name}" target="{target}"{attrs}/>"#
+                )
+            }
+            file_type => {
+                let element = match file_type {
+                    FileType::BlockDevice => "block",
+                    FileType::CharDevice => "char",
+                    FileType::Fifo => "fifo",
+                    FileType::Socket => "socket",
+                    _ => "file",
+                };
+
+                writeln!(out, r#"{indent}<{element} name="{name}"{attrs}/>"#)
+            }
+        }
+    }
+}
@@ -0,0 +1,266 @@
+use feruca::Collator;
+use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::ffi::OsStr;
+use std::fs::Metadata;
+use std::path::Path;
+use std::time::SystemTime;
+
+/// The order of entries within each directory.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Sort {
+    pub by: SortBy,
+    /// Reverse the order.
+    pub reverse: bool,
+    /// Where directories go relative to other entries, regardless of `by` and `reverse`.
+    pub directories: DirectoryOrder,
+    /// How names are compared, both for [`SortBy::Name`] and to break ties.
+    pub collation: Collation,
+}
+
+/// Where directories are placed among their siblings.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DirectoryOrder {
+    /// Mixed in with other entries.
+    #[default]
+    Mixed,
+    /// Before other entries.
+    First,
+    /// After other entries.
+    Last,
+}
+
+/// The key entries are sorted by, with ties broken by name.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum SortBy {
+    /// By name
+    #[default]
+    Name,
+    /// Largest first
+    Size,
+    /// Most recently modified first
+    Mtime,
+    /// Most recently changed first
+    Ctime,
+    /// By extension, then by name
+    Extension,
+    /// By name, comparing runs of digits numerically
+    Version,
+    /// In the order the filesystem returns entries
+    None,
+}
+
+/// How names are compared.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum Collation {
+    /// Byte-wise
+    #[default]
+    Bytes,
+    /// Ignoring case
+    CaseInsensitive,
+    /// Ignoring case, comparing runs of digits numerically
+    Natural,
+    /// Following the Unicode Collation Algorithm
+    Unicode,
+}
+
+impl Collation {
+    pub fn compare(&self, a: &OsStr, b: &OsStr) -> Ordering {
+        let ordering = match self {
+            Self::Bytes => Ordering::Equal,
+            Self::CaseInsensitive => a
+                .to_string_lossy()
+                .to_lowercase()
+                .cmp(&b.to_string_lossy().to_lowercase()),
+            Self::Natural => version_cmp(
+                &a.to_string_lossy().to_lowercase(),
+                &b.to_string_lossy().to_lowercase(),
+            ),
+            Self::Unicode => COLLATOR.with_borrow_mut(|collator| {
+                collator.collate(a.as_encoded_bytes(), b.as_encoded_bytes())
+            }),
+        };
+
+        ordering.then_with(|| a.cmp(b))
+    }
+}
+
+thread_local! {
+    static COLLATOR: RefCell<Collator> = RefCell::new(Collator::default());
+}
+
+/// The properties of an entry that [`Sort`] compares.
+#[derive(Debug, Clone, Copy)]
+pub struct SortKey<'a> {
+    pub name: &'a OsStr,
+    pub is_dir: bool,
+    pub size: u64,
+    pub modified: Option<SystemTime>,
+    pub changed: Option<SystemTime>,
+}
+
+impl<'a> SortKey<'a> {
+    /// The key for an entry known only by name, enough for sorts that do not
+    /// [need metadata](Sort::needs_metadata).
+    pub fn from_name(name: &'a OsStr) -> Self {
+        Self {
+            name,
+            is_dir: false,
+            size: 0,
+            modified: None,
+            changed: None,
+        }
+    }
+
+    /// Reads the key for `path` from the filesystem, without following symlinks.
+    ///
+    /// This stats `path`, so prefer [`SortKey::from_name`] where it suffices.
+    pub fn from_path(path: &'a Path) -> Self {
+        let metadata = path.symlink_metadata().ok();
+
+        Self {
+            name: path.file_name().unwrap_or(path.as_os_str()),
+            is_dir: metadata.as_ref().is_some_and(Metadata::is_dir),
+            size: metadata.as_ref().map(Metadata::len).unwrap_or_default(),
+            modified: metadata.as_ref().and_then(|m| m.modified().ok()),
+            changed: metadata.as_ref().and_then(changed),
+        }
+    }
+}
+
+impl Sort {
+    /// Whether entries are left in the order the filesystem returns them.
+    pub fn is_none(&self) -> bool {
+        self.by == SortBy::None && self.directories == DirectoryOrder::Mixed
+    }
+
+    /// Whether comparing entries needs more than their names.
+    pub fn needs_metadata(&self) -> bool {
+        matches!(self.by, SortBy::Size | SortBy::Mtime | SortBy::Ctime)
+            || self.directories != DirectoryOrder::Mixed
+    }
+
+    pub fn compare(&self, a: &SortKey, b: &SortKey) -> Ordering {
+        let group = match self.directories {
+            DirectoryOrder::Mixed => Ordering::Equal,
+            DirectoryOrder::First => b.is_dir.cmp(&a.is_dir),
+            DirectoryOrder::Last => a.is_dir.cmp(&b.is_dir),
+        };
+
+        group.then_with(|| self.compare_keys(a, b))
+    }
+
+    fn compare_keys(&self, a: &SortKey, b: &SortKey) -> Ordering {
+        let ordering = match self.by {
+            SortBy::None => return Ordering::Equal,
+            SortBy::Name => Ordering::Equal,
+            SortBy::Size => b.size.cmp(&a.size),
+            SortBy::Mtime => b.modified.cmp(&a.modified),
+            SortBy::Ctime => b.changed.cmp(&a.changed),
+            SortBy::Extension => extension(a.name).cmp(extension(b.name)),
+            SortBy::Version => version_cmp(&a.name.to_string_lossy(), &b.name.to_string_lossy()),
+        }
+        .then_with(|| self.collation.compare(a.name, b.name));
+
+        if self.reverse {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    }
+}
+
+/// Returns the time of the last status change, falling back to the creation time.
+pub fn changed(metadata: &Metadata) -> Option<SystemTime> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        use std::time::Duration;
+
+        let secs = u64::try_from(metadata.ctime()).ok()?;
+        let nanos = u32::try_from(metadata.ctime_nsec()).ok()?;
+
+        SystemTime::UNIX_EPOCH.checked_add(Duration::new(secs, nanos))
+    }
+
+    #[cfg(not(unix))]
+    {
+        metadata.created().ok()
+    }
+}
+
+fn extension(name: &OsStr) -> &OsStr {
+    Path::new(name).extension().unwrap_or_default()
+}
+
+/// Compares two names like `sort -V`, so that `v1.9` sorts before `v1.10`.
+fn version_cmp(a: &str, b: &str) -> Ordering {
+    let mut a = a.chars().peekable();
+    let mut b = b.chars().peekable();
+
+    loop {
+        match (a.peek(), b.peek()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) if x.is_ascii_digit() && y.is_ascii_digit() => {
+                let x = take_digits(&mut a);
+                let y = take_digits(&mut b);
+
+                let ordering = x.len().cmp(&y.len()).then_with(|| x.cmp(&y));
+
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+            }
+            (Some(x), Some(y)) => {
+                let ordering = x.cmp(y);
+
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+
+                a.next();
+                b.next();
+            }
+        }
+    }
+}
+
+/// Consumes a run of digits, returning it without leading zeros.
+fn take_digits(chars: &mut std::iter::Peekable<std::str::Chars>) -> String {
+    let mut digits = String::new();
+
+    while let Some(c) = chars.next_if(char::is_ascii_digit) {
+        if !(digits.is_empty() && c == '0') {
+            digits.push(c);
+        }
+    }
+
+    digits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn version_cmp_compares_digit_runs_numerically() {
+        assert_eq!(version_cmp("v1.9", "v1.10"), Ordering::Less);
+        assert_eq!(version_cmp("v1.10", "v1.9"), Ordering::Greater);
+        assert_eq!(version_cmp("v2.0", "v10.0"), Ordering::Less);
+    }
+
+    #[test]
+    fn version_cmp_ignores_leading_zeros() {
+        assert_eq!(version_cmp("v007", "v7"), Ordering::Equal);
+        assert_eq!(version_cmp("v09", "v10"), Ordering::Less);
+    }
+
+    #[test]
+    fn version_cmp_handles_digits_at_the_end() {
+        assert_eq!(version_cmp("file2", "file10"), Ordering::Less);
+        assert_eq!(version_cmp("file", "file1"), Ordering::Less);
+        assert_eq!(version_cmp("file1", "file1"), Ordering::Equal);
+    }
+}
@@ -0,0 +1,485 @@
+use crate::config::WalkConfig;
+use crate::sort::{self, Sort, SortKey};
+use serde::Serialize;
+use std::ffi::OsStr;
+use std::fs::Metadata;
+use std::time::SystemTime;
+
+/// The walked hierarchy together with its summary counts.
+#[derive(Debug, Serialize)]
+pub struct Tree {
+    #[serde(rename = "tree")]
+    pub root: Node,
+    pub summary: Summary,
+}
+
+impl Tree {
+    /// Walks the directory described by `config`, passing any errors to `on_error`.
+    ///
+    /// Fails only if `config` is invalid, see [`WalkConfig::build`].
+    pub fn from_walk(
+        config: &WalkConfig,
+        mut on_error: impl FnMut(ignore::Error),
+    ) -> Result<Self, ignore::Error> {
+        let walker = config.build_unsorted_by_metadata()?;
+
+        let mut root = Node::new(config.dir.to_string(), FileType::Directory);
+
+        if let Ok(metadata) = std::fs::metadata(&config.dir) {
+            root.set_metadata(&metadata);
+        }
+
+        let mut stack = vec![root];
+
+        for entry in walker.skip(1) {
+            match entry {
+                Ok(entry) => {
+                    let entry_depth = entry.depth();
+
+                    while stack.len() > entry_depth {
+                        close_directory(&mut stack);
+                    }
+
+                    match entry.file_type() {
+                        Some(file_type) => {
+                            let file_type = file_type.into();
+
+                            let file_name = entry.file_name().to_string_lossy();
+
+                            let mut node = Node::new(file_name.into(), file_type);
+
+                            if let Ok(metadata) = entry.metadata() {
+                                node.set_metadata(&metadata);
+                            }
+
+                            match file_type {
+                                FileType::Directory => {
+                                    stack.push(node);
+
+                                    continue;
+                                }
+                                FileType::Symlink => {
+                                    node.target = match entry.path().read_link() {
+                                        Ok(s) => Some(s.to_string_lossy().into()),
+                                        Err(err) => {
+                                            on_error(err.into());
+                                            continue;
+                                        }
+                                    };
+                                }
+                                _ => {}
+                            }
+
+                            stack
+                                .last_mut()
+                                .expect("the root directory is never popped")
+                                .children
+                                .push(node);
+                        }
+                        None => continue,
+                    }
+                }
+                Err(err) => on_error(err),
+            }
+        }
+
+        while stack.len() > 1 {
+            close_directory(&mut stack);
+        }
+
+        let mut root = stack.pop().expect("the root directory is never popped");
+
+        if config.sort.needs_metadata() {
+            root.sort(&config.sort);
+        }
+
+        let mut summary = Summary::count(&root);
+
+        if config.dirs_only {
+            summary.files = None;
+        }
+
+        Ok(Self { root, summary })
+    }
+
+    /// Removes directories that have no files anywhere below them, except the root.
+    pub fn prune(&mut self) {
+        self.root.prune();
+
+        self.recount();
+    }
+
+    /// Replaces the hierarchy with a flat list of every non-directory entry,
+    /// each named by its path relative to the root.
+    pub fn flatten_files(&mut self) {
+        let mut files = Vec::new();
+
+        for child in std::mem::take(&mut self.root.children) {
+            child.flatten_files(None, &mut files);
+        }
+
+        self.root.children = files;
+
+        self.recount();
+        self.summary.directories = None;
+    }
+
+    /// Leaves out the children of directories with more than `limit` entries.
+    ///
+    /// With `elide`, the first `limit` children are kept instead of none.
+    /// Otherwise the root is always opened. The summary still counts every entry.
+    pub fn limit_entries(&mut self, limit: usize, elide: bool) {
+        if elide {
+            self.root.limit_entries(limit, limit);
+        } else {
+            for child in &mut self.root.children {
+                child.limit_entries(limit, 0);
+            }
+        }
+    }
+
+    /// Merges each chain of directories that only contain one subdirectory into
+    /// a single node named by their joined path, like `src/main/java`.
+    pub fn compact(&mut self) {
+        for child in &mut self.root.children {
+            child.compact();
+        }
+    }
+
+    /// Recounts the entries, keeping any counts that are not shown hidden.
+    fn recount(&mut self) {
+        let summary = Summary::count(&self.root);
+
+        self.summary = Summary {
+            directories: self.summary.directories.and(summary.directories),
+            files: self.summary.files.and(summary.files),
+            ..summary
+        };
+    }
+
+    /// Replaces the size of every directory with the total size of itself and its descendants.
+    pub fn accumulate_sizes(&mut self) {
+        self.root.accumulate_sizes();
+    }
+
+    /// Reorders the entries of every directory, for keys the walker could not
+    /// know up front, such as sizes after [`Tree::accumulate_sizes`].
+    pub fn sort(&mut self, sort: &Sort) {
+        if !sort.is_none() {
+            self.root.sort(sort);
+        }
+    }
+}
+
+/// Pops the innermost open directory and attaches it to its parent.
+fn close_directory(stack: &mut Vec<Node>) {
+    let dir = stack.pop().expect("the root directory is never popped");
+
+    stack
+        .last_mut()
+        .expect("the root directory is never popped")
+        .children
+        .push(dir);
+}
+
+/// A single entry in the walked tree.
+#[derive(Debug, Serialize)]
+pub struct Node {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub file_type: FileType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+    /// Size in bytes, as reported by the entry's own metadata, or the cumulative
+    /// size after [`Tree::accumulate_sizes`].
+    pub size: u64,
+    /// Time of the last modification.
+    #[serde(skip)]
+    pub modified: Option<SystemTime>,
+    /// Time of the last status change.
+    #[serde(skip)]
+    pub changed: Option<SystemTime>,
+    /// File type and permission bits, as in `st_mode`, or zero where unsupported.
+    #[serde(skip)]
+    pub mode: u32,
+    /// Numeric id of the owning user, where supported.
+    #[serde(skip)]
+    pub uid: Option<u32>,
+    /// Numeric id of the owning group, where supported.
+    #[serde(skip)]
+    pub gid: Option<u32>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<Node>,
+    /// Number of children left out of `children` by [`Tree::limit_entries`].
+    #[serde(skip_serializing_if = "is_zero")]
+    pub omitted: usize,
+}
+
+fn is_zero(n: &usize) -> bool {
+    *n == 0
+}
+
+impl Node {
+    pub fn new(name: String, file_type: FileType) -> Self {
+        Self {
+            name,
+            file_type,
+            target: None,
+            size: 0,
+            modified: None,
+            changed: None,
+            mode: 0,
+            uid: None,
+            gid: None,
+            children: Vec::new(),
+            omitted: 0,
+        }
+    }
+
+    fn set_metadata(&mut self, metadata: &Metadata) {
+        self.size = metadata.len();
+        self.modified = metadata.modified().ok();
+        self.changed = sort::changed(metadata);
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::MetadataExt;
+
+            self.mode = metadata.mode();
+            self.uid = Some(metadata.uid());
+            self.gid = Some(metadata.gid());
+        }
+    }
+
+    /// Whether this is a regular file that anyone may execute.
+    pub fn is_executable(&self) -> bool {
+        self.file_type == FileType::File && self.mode & 0o111 != 0
+    }
+
+    fn sort_key(&self) -> SortKey<'_> {
+        SortKey {
+            name: OsStr::new(&self.name),
+            is_dir: self.file_type == FileType::Directory,
+            size: self.size,
+            modified: self.modified,
+            changed: self.changed,
+        }
+    }
+
+    fn sort(&mut self, sort: &Sort) {
+        self.children
+            .sort_by(|a, b| sort.compare(&a.sort_key(), &b.sort_key()));
+
+        for child in &mut self.children {
+            child.sort(sort);
+        }
+    }
+
+    fn flatten_files(mut self, parent: Option<&str>, files: &mut Vec<Node>) {
+        if let Some(parent) = parent {
+            self.name = format!("{parent}/{}", self.name);
+        }
+
+        if self.file_type == FileType::Directory {
+            for child in std::mem::take(&mut self.children) {
+                child.flatten_files(Some(&self.name), files);
+            }
+        } else {
+            files.push(self);
+        }
+    }
+
+    fn compact(&mut self) {
+        if self.file_type != FileType::Directory {
+            return;
+        }
+
+        while let [only] = self.children.as_slice() {
+            if only.file_type != FileType::Directory || self.omitted > 0 {
+                break;
+            }
+
+            let only = self.children.pop().expect("there is exactly one child");
+
+            self.name = format!("{}/{}", self.name, only.name);
+            self.children = only.children;
+            self.omitted = only.omitted;
+        }
+
+        for child in &mut self.children {
+            child.compact();
+        }
+    }
+
+    fn limit_entries(&mut self, limit: usize, keep: usize) {
+        if self.children.len() > limit {
+            self.omitted = self.children.len() - keep;
+            self.children.truncate(keep);
+        }
+
+        for child in &mut self.children {
+            child.limit_entries(limit, keep);
+        }
+    }
+
+    fn prune(&mut self) {
+        for child in &mut self.children {
+            child.prune();
+        }
+
+        self.children
+            .retain(|child| child.file_type != FileType::Directory || !child.children.is_empty());
+    }
+
+    fn accumulate_sizes(&mut self) -> u64 {
+        for child in &mut self.children {
+            self.size += child.accumulate_sizes();
+        }
+
+        self.size
+    }
+}
+
+/// Counts printed below the tree.
+///
+/// A count is `None` when that kind of entry is not listed at all.
+#[derive(Debug, Default, Serialize)]
+pub struct Summary {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub directories: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub files: Option<usize>,
+    pub symlinks: usize,
+    pub block_devices: usize,
+    pub char_devices: usize,
+    pub fifos: usize,
+    pub sockets: usize,
+}
+
+impl Summary {
+    /// Counts the entries below `root`.
+    pub fn count(root: &Node) -> Self {
+        let mut summary = Self {
+            directories: Some(0),
+            files: Some(0),
+            ..Self::default()
+        };
+
+        summary.add_children(root);
+
+        summary
+    }
+
+    fn add_children(&mut self, node: &Node) {
+        for child in &node.children {
+            let counter = match child.file_type {
+                FileType::Directory => self.directories.as_mut(),
+                _ => self.files.as_mut(),
+            };
+
+            if let Some(count) = counter {
+                *count += 1;
+            }
+
+            match child.file_type {
+                FileType::Symlink => self.symlinks += 1,
+                FileType::BlockDevice => self.block_devices += 1,
+                FileType::CharDevice => self.char_devices += 1,
+                FileType::Fifo => self.fifos += 1,
+                FileType::Socket => self.sockets += 1,
+                FileType::Directory | FileType::File | FileType::Other => {}
+            }
+
+            self.add_children(child);
+        }
+    }
+}
+
+impl std::fmt::Display for Summary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut parts = Vec::new();
+
+        if let Some(directories) = self.directories {
+            parts.push(format!("{} directories", directories));
+        }
+
+        if let Some(files) = self.files {
+            parts.push(format!("{} files", files));
+        }
+
+        let special = [
+            (self.symlinks, "symlinks"),
+            (self.block_devices, "block devices"),
+            (self.char_devices, "character devices"),
+            (self.fifos, "fifos"),
+            (self.sockets, "sockets"),
+        ];
+
+        for (count, kind) in special {
+            if count > 0 {
+                parts.push(format!("{} {}", count, kind));
+            }
+        }
+
+        write!(f, "{}", parts.join(", "))
+    }
+}
+
+/// The kind of a walked entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FileType {
+    Directory,
+    File,
+    Symlink,
+    BlockDevice,
+    CharDevice,
+    Fifo,
+    Socket,
+    Other,
+}
+
+impl FileType {
+    /// The name used for this kind in JSON and as a CSS class.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Directory => "directory",
+            Self::File => "file",
+            Self::Symlink => "symlink",
+            Self::BlockDevice => "block_device",
+            Self::CharDevice => "char_device",
+            Self::Fifo => "fifo",
+            Self::Socket => "socket",
+            Self::Other => "other",
+        }
+    }
+}
+
+impl From<std::fs::FileType> for FileType {
+    fn from(file_type: std::fs::FileType) -> Self {
+        if file_type.is_dir() {
+            return Self::Directory;
+        } else if file_type.is_file() {
+            return Self::File;
+        } else if file_type.is_symlink() {
+            return Self::Symlink;
+        }
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::FileTypeExt;
+
+            if file_type.is_block_device() {
+                return Self::BlockDevice;
+            } else if file_type.is_char_device() {
+                return Self::CharDevice;
+            } else if file_type.is_fifo() {
+                return Self::Fifo;
+            } else if file_type.is_socket() {
+                return Self::Socket;
+            }
+        }
+
+        Self::Other
+    }
+}